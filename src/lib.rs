@@ -1,4 +1,5 @@
-use std::fmt::{Show, Formatter, Result};
+use std::fmt::{Show, Formatter, Result as FmtResult};
+use std::error::{Error, FromError};
 
 #[deriving(PartialOrd, PartialEq, Clone)]
 pub enum Value {
@@ -6,10 +7,11 @@ pub enum Value {
     Symbol(String),
     String_(String),
     Number(f64),
+    Integer(i64),
 }
 
 impl Show for Value {
-    fn fmt(&self, f: &mut Formatter) -> Result {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
         match *self {
             List(ref vals) => {
                 try!(write!(f, "("));
@@ -22,82 +24,353 @@ impl Show for Value {
                 write!(f, ")")
             },
             Symbol(ref val) => val.fmt(f),
-            String_(ref val) => write!(f, "\"{}\"", val),
+            String_(ref val) => {
+                try!(write!(f, "\""));
+                for c in val.as_slice().chars() {
+                    match c {
+                        '\n' => try!(write!(f, "\\n")),
+                        '\t' => try!(write!(f, "\\t")),
+                        '\r' => try!(write!(f, "\\r")),
+                        '\\' => try!(write!(f, "\\\\")),
+                        '"' => try!(write!(f, "\\\"")),
+                        c => try!(write!(f, "{}", c)),
+                    }
+                }
+                write!(f, "\"")
+            },
+            // A whole-valued float gets a trailing `.0` so it re-parses as a `Number`, not an `Integer`.
+            Number(ref val) if val.to_string().as_slice().chars().all(|c| c.is_digit() || c == '-') => write!(f, "{}.0", val),
             Number(ref val) => val.fmt(f),
+            Integer(ref val) => val.fmt(f),
         }
     }
 }
 
-#[deriving(Show, PartialEq)]
+#[deriving(Show, PartialEq, Clone)]
 pub enum Token {
     LPAREN,
     RPAREN,
-    MINUS,
+    INT(i64),
     NUM(f64),
     SYM(String),
     STR(String),
 }
 
+/// A single location in the source text, one-indexed like most editors.
+#[deriving(Show, PartialEq, Clone)]
+pub struct Position {
+    pub line: uint,
+    pub col: uint,
+}
+
+/// The range of source text a token, error, or `Value` came from.
+#[deriving(Show, PartialEq, Clone)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// A `T` together with the span of source text it was parsed from.
+///
+/// When `T` is `Value` (see `parse_spanned`), only the form the `Spanned`
+/// wraps is covered — `List` still holds plain `Value`s, so spans don't
+/// reach into nested elements.
+#[deriving(PartialEq, Clone)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T: Show> Show for Spanned<T> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} at {}", self.node, self.start_str())
+    }
+}
+
+impl<T> Spanned<T> {
+    fn start_str(&self) -> String {
+        format!("line {}, col {}", self.span.start.line, self.span.start.col)
+    }
+}
+
+/// Failures while turning raw characters into `Token`s.
+#[deriving(Show, PartialEq, Clone)]
+pub enum LexErrorKind {
+    UnexpectedChar(char),
+    MalformedNumber(String),
+    UnterminatedString,
+    MalformedEscapeSequence(String),
+}
+
+impl Error for LexErrorKind {
+    fn description(&self) -> &str {
+        match *self {
+            UnexpectedChar(_) => "unexpected character",
+            MalformedNumber(_) => "malformed number",
+            UnterminatedString => "unterminated string literal",
+            MalformedEscapeSequence(_) => "malformed escape sequence",
+        }
+    }
+}
+
+/// Value of a hex digit, or `None` if `c` isn't one.
+fn hex_value(c: char) -> Option<u32> {
+    match c {
+        '0' .. '9' => Some(c as u32 - '0' as u32),
+        'a' .. 'f' => Some(c as u32 - 'a' as u32 + 10),
+        'A' .. 'F' => Some(c as u32 - 'A' as u32 + 10),
+        _ => None,
+    }
+}
+
+pub type LexError = Spanned<LexErrorKind>;
+
+impl Error for LexError {
+    fn description(&self) -> &str {
+        self.node.description()
+    }
+}
+
+/// Failures while turning a `Token` stream into a `Value`.
+#[deriving(Show, PartialEq, Clone)]
+pub enum ParseErrorKind {
+    Lex(LexErrorKind),
+    UnexpectedEof,
+    UnbalancedParen,
+}
+
+impl Error for ParseErrorKind {
+    fn description(&self) -> &str {
+        match *self {
+            Lex(ref e) => e.description(),
+            UnexpectedEof => "unexpected end of input",
+            UnbalancedParen => "unbalanced parenthesis",
+        }
+    }
+}
+
+pub type ParseError = Spanned<ParseErrorKind>;
+
+impl Error for ParseError {
+    fn description(&self) -> &str {
+        self.node.description()
+    }
+}
+
+impl FromError<LexError> for ParseError {
+    fn from_err(err: LexError) -> ParseError {
+        Spanned { node: Lex(err.node), span: err.span }
+    }
+}
+
 struct Lexer<R> {
     stream: std::iter::Peekable<char, R>,
+    line: uint,
+    col: uint,
 }
 
 fn is_ident(c: char) -> bool {
     c.is_alphabetic() || c == '-'
 }
 
-impl<R: Iterator<char>> Iterator<Token> for Lexer<R> {
-    fn next(&mut self) -> Option<Token> {
+impl<R: Iterator<char>> Lexer<R> {
+    fn pos(&self) -> Position {
+        Position { line: self.line, col: self.col }
+    }
+
+    /// Consumes one character from the stream, keeping `line`/`col` in sync.
+    /// `#` comments and everything else route through here so spans stay accurate.
+    fn bump(&mut self) -> Option<char> {
+        match self.stream.next() {
+            Some(c) => {
+                if c == '\n' {
+                    self.line += 1;
+                    self.col = 1;
+                } else {
+                    self.col += 1;
+                }
+                Some(c)
+            },
+            None => None,
+        }
+    }
+
+    fn lex_ident(&mut self, first: char) -> Token {
+        let mut res = String::new();
+        res.push_char(first);
+        while self.stream.peek().map_or(false, |&c| c.is_alphabetic() || c == '-') {
+            res.push_char(self.bump().unwrap());
+        }
+        SYM(res)
+    }
+
+    /// Lexes a full numeric literal after its optional leading sign and first
+    /// significant character (a digit or `.`) have already been taken off the
+    /// stream. Handles an optional fractional part and an optional `e`/`E`
+    /// exponent with its own sign, yielding `INT` for whole numbers and `NUM`
+    /// for anything with a `.` or exponent.
+    fn lex_number(&mut self, first: char, sign: Option<char>) -> Result<Token, LexErrorKind> {
+        let mut res = String::new();
+        match sign {
+            Some('-') => res.push_char('-'),
+            _ => {},
+        }
+
+        let mut is_float = false;
+        if first != '.' {
+            res.push_char(first);
+            while self.stream.peek().map_or(false, |&c| c.is_digit()) {
+                res.push_char(self.bump().unwrap());
+            }
+            if self.stream.peek().map_or(false, |&c| c == '.') {
+                is_float = true;
+                res.push_char(self.bump().unwrap());
+                while self.stream.peek().map_or(false, |&c| c.is_digit()) {
+                    res.push_char(self.bump().unwrap());
+                }
+            }
+        } else {
+            is_float = true;
+            res.push_char('0');
+            res.push_char('.');
+            while self.stream.peek().map_or(false, |&c| c.is_digit()) {
+                res.push_char(self.bump().unwrap());
+            }
+        }
+
+        // A second `.` (as in `1.2.3`) is malformed; consume the rest of the
+        // literal so the error carries the whole offending text.
+        if self.stream.peek().map_or(false, |&c| c == '.') {
+            res.push_char(self.bump().unwrap());
+            while self.stream.peek().map_or(false, |&c| c.is_digit() || c == '.') {
+                res.push_char(self.bump().unwrap());
+            }
+            return Err(MalformedNumber(res));
+        }
+
+        if self.stream.peek().map_or(false, |&c| c == 'e' || c == 'E') {
+            is_float = true;
+            res.push_char(self.bump().unwrap());
+            if self.stream.peek().map_or(false, |&c| c == '+' || c == '-') {
+                res.push_char(self.bump().unwrap());
+            }
+            let mut exponent_digits = 0u;
+            while self.stream.peek().map_or(false, |&c| c.is_digit()) {
+                res.push_char(self.bump().unwrap());
+                exponent_digits += 1;
+            }
+            if exponent_digits == 0 {
+                return Err(MalformedNumber(res));
+            }
+        }
+
+        if is_float {
+            match from_str::<f64>(res.as_slice()) {
+                Some(val) => Ok(NUM(val)),
+                None => Err(MalformedNumber(res)),
+            }
+        } else {
+            match from_str::<i64>(res.as_slice()) {
+                Some(val) => Ok(INT(val)),
+                None => Err(MalformedNumber(res)),
+            }
+        }
+    }
+}
+
+impl<R: Iterator<char>> Iterator<Result<Spanned<Token>, LexError>> for Lexer<R> {
+    fn next(&mut self) -> Option<Result<Spanned<Token>, LexError>> {
         loop {
-            match self.stream.next() {
+            let start = self.pos();
+            match self.bump() {
                 None => return None,
                 Some(c) => {
                     match c {
-                        '(' => return Some(LPAREN),
-                        ')' => return Some(RPAREN),
-                        '-' => return Some(MINUS),
+                        '(' => return Some(Ok(Spanned { node: LPAREN, span: Span { start: start, end: self.pos() } })),
+                        ')' => return Some(Ok(Spanned { node: RPAREN, span: Span { start: start, end: self.pos() } })),
+                        '-' | '+' => {
+                            match self.stream.peek() {
+                                Some(&d) if d.is_digit() || d == '.' => {
+                                    let first = self.bump().unwrap();
+                                    match self.lex_number(first, Some(c)) {
+                                        Ok(tok) => return Some(Ok(Spanned { node: tok, span: Span { start: start, end: self.pos() } })),
+                                        Err(kind) => return Some(Err(Spanned { node: kind, span: Span { start: start, end: self.pos() } })),
+                                    }
+                                },
+                                _ if c == '-' => {
+                                    return Some(Ok(Spanned { node: self.lex_ident(c), span: Span { start: start, end: self.pos() } }));
+                                },
+                                _ => return Some(Err(Spanned { node: UnexpectedChar(c), span: Span { start: start, end: self.pos() } })),
+                            }
+                        },
                         '"' => {
                             let mut res = String::new();
-                            while self.stream.peek().map_or(false, |&c| c != '"') {
-                                res.push_char(self.stream.next().unwrap());
+                            loop {
+                                match self.bump() {
+                                    None => return Some(Err(Spanned { node: UnterminatedString, span: Span { start: start, end: self.pos() } })),
+                                    Some('"') => break,
+                                    Some('\\') => {
+                                        match self.bump() {
+                                            None => return Some(Err(Spanned { node: UnterminatedString, span: Span { start: start, end: self.pos() } })),
+                                            Some('n') => res.push_char('\n'),
+                                            Some('t') => res.push_char('\t'),
+                                            Some('r') => res.push_char('\r'),
+                                            Some('\\') => res.push_char('\\'),
+                                            Some('"') => res.push_char('"'),
+                                            Some('u') => {
+                                                if self.bump() != Some('{') {
+                                                    return Some(Err(Spanned { node: MalformedEscapeSequence(String::new()), span: Span { start: start, end: self.pos() } }));
+                                                }
+                                                let mut seq = String::new();
+                                                let mut well_formed = true;
+                                                loop {
+                                                    match self.bump() {
+                                                        Some('}') => break,
+                                                        // \u{10FFFF} is the highest valid codepoint, six hex
+                                                        // digits; capping here keeps the accumulator below
+                                                        // u32 overflow instead of silently wrapping around.
+                                                        Some(c) if hex_value(c).is_some() && seq.len() < 6 => seq.push_char(c),
+                                                        _ => { well_formed = false; break; },
+                                                    }
+                                                }
+                                                let code = if well_formed {
+                                                    let mut val: u32 = 0;
+                                                    for c in seq.as_slice().chars() {
+                                                        val = val * 16 + hex_value(c).unwrap();
+                                                    }
+                                                    std::char::from_u32(val)
+                                                } else {
+                                                    None
+                                                };
+                                                match code {
+                                                    Some(c) => res.push_char(c),
+                                                    None => return Some(Err(Spanned { node: MalformedEscapeSequence(seq), span: Span { start: start, end: self.pos() } })),
+                                                }
+                                            },
+                                            Some(c) => {
+                                                let mut seq = String::new();
+                                                seq.push_char(c);
+                                                return Some(Err(Spanned { node: MalformedEscapeSequence(seq), span: Span { start: start, end: self.pos() } }));
+                                            },
+                                        }
+                                    },
+                                    Some(c) => res.push_char(c),
+                                }
                             }
-                            assert!(self.stream.next().unwrap() == '"');
-                            return Some(STR(res));
+                            return Some(Ok(Spanned { node: STR(res), span: Span { start: start, end: self.pos() } }));
                         },
                         c if is_ident(c) => {
-                            let mut res = String::new();
-                            res.push_char(c);
-                            while self.stream.peek().map_or(false, |&c| c.is_alphabetic() || c == '-') {
-                                res.push_char(self.stream.next().unwrap());
-                            }
-                            return Some(SYM(res));
+                            return Some(Ok(Spanned { node: self.lex_ident(c), span: Span { start: start, end: self.pos() } }));
                         },
                         c @ '0' .. '9' | c @ '.' => {
-                            let mut res = String::new();
-                            if c != '.' {
-                                res.push_char(c);
-                                while self.stream.peek().map_or(false, |&c| c.is_digit()) {
-                                    res.push_char(self.stream.next().unwrap());
-                                }
-                                if self.stream.peek().map_or(false, |&c| c == '.') {
-                                    res.push_char(self.stream.next().unwrap());
-                                    while self.stream.peek().map_or(false, |&c| c.is_digit()) {
-                                        res.push_char(self.stream.next().unwrap());
-                                    }
-                                }
-                            } else {
-                                res.push_char('0');
-                                res.push_char('.');
-                                while self.stream.peek().map_or(false, |&c| c.is_digit()) {
-                                    res.push_char(self.stream.next().unwrap());
-                                }
+                            match self.lex_number(c, None) {
+                                Ok(tok) => return Some(Ok(Spanned { node: tok, span: Span { start: start, end: self.pos() } })),
+                                Err(kind) => return Some(Err(Spanned { node: kind, span: Span { start: start, end: self.pos() } })),
                             }
-
-                            return Some(NUM(from_str(res.as_slice()).unwrap()));
                         },
                         '#' => {
                             while self.stream.peek().map_or(false, |&c| c != '\n') {
-                                self.stream.next();
+                                self.bump();
                             }
                             continue;
                         },
@@ -105,8 +378,7 @@ impl<R: Iterator<char>> Iterator<Token> for Lexer<R> {
                             continue;
                         }
                         c => {
-                            println!("Invalid character: {}", c);
-                            return None;
+                            return Some(Err(Spanned { node: UnexpectedChar(c), span: Span { start: start, end: self.pos() } }));
                         }
                     }
                 }
@@ -115,75 +387,125 @@ impl<R: Iterator<char>> Iterator<Token> for Lexer<R> {
     }
 }
 
-struct Parser<R> {
-    lexer: std::iter::Peekable<Token, Lexer<R>>,
-    stack: Vec<Value>,
+/// Reads a stream of characters as a sequence of top-level `Value`s, one per
+/// `next()` call, so a file or REPL buffer with several forms in it (e.g.
+/// `(a) (b) 42`) can be consumed without throwing away anything past the first.
+pub struct Parser<R> {
+    lexer: std::iter::Peekable<Result<Spanned<Token>, LexError>, Lexer<R>>,
+    last_pos: Position,
 }
 
 impl<R: Iterator<char>> Parser<R> {
-    fn expect_number(&mut self) -> f64 {
-        match self.lexer.next().unwrap() {
-            NUM(val) => return val,
-            tok => fail!("Expected number, found {}", tok),
-        }
+    fn eof_error(&self) -> ParseError {
+        Spanned { node: UnexpectedEof, span: Span { start: self.last_pos, end: self.last_pos } }
     }
 
-    fn parse(&mut self) -> Option<Value> {
+    fn next_token(&mut self) -> Result<Spanned<Token>, ParseError> {
         match self.lexer.next() {
-            None => return None,
-            Some(tok) => {
-                match tok {
-                    NUM(val) => return Some(Number(val)),
-                    SYM(val) => return Some(Symbol(val)),
-                    STR(val) => return Some(String_(val)),
-                    MINUS => return Some(Number(-self.expect_number())),
-                    LPAREN => {
-                        while self.lexer.peek().map_or(false, |tok| tok != &RPAREN) {
-                            let mut st = Vec::new();
-                            std::mem::swap(&mut self.stack, &mut st);
-
-                            let next = self.parse().expect("Needed an element");
-
-                            std::mem::swap(&mut self.stack, &mut st);
-
-                            self.stack.push(next);
-                        }
-                        assert_eq!(self.lexer.next().unwrap(), RPAREN);
-                        let mut st = Vec::new();
-                        std::mem::swap(&mut self.stack, &mut st);
-                        return Some(List(st));
-                    },
-                    RPAREN => {
-                        println!("Unbalanced parenthesis!");
-                        return None;
+            None => Err(self.eof_error()),
+            Some(Ok(tok)) => {
+                self.last_pos = tok.span.end;
+                Ok(tok)
+            },
+            Some(Err(e)) => Err(FromError::from_err(e)),
+        }
+    }
+
+    /// Parses one top-level form, returning it together with the span it covers.
+    fn parse_inner(&mut self) -> Result<(Value, Span), ParseError> {
+        let tok = try!(self.next_token());
+        match tok.node {
+            INT(val) => Ok((Integer(val), tok.span)),
+            NUM(val) => Ok((Number(val), tok.span)),
+            SYM(val) => Ok((Symbol(val), tok.span)),
+            STR(val) => Ok((String_(val), tok.span)),
+            LPAREN => {
+                let mut elems = Vec::new();
+                loop {
+                    match self.lexer.peek() {
+                        Some(&Ok(ref t)) if t.node == RPAREN => break,
+                        Some(&Ok(_)) => {
+                            let (next, _) = try!(self.parse_inner());
+                            elems.push(next);
+                        },
+                        Some(&Err(_)) => { try!(self.next_token()); },
+                        None => return Err(self.eof_error()),
                     }
                 }
-            }
+                let close = try!(self.next_token());
+                Ok((List(elems), Span { start: tok.span.start, end: close.span.end }))
+            },
+            RPAREN => Err(Spanned { node: UnbalancedParen, span: tok.span }),
+        }
+    }
+
+    fn parse(&mut self) -> Result<Value, ParseError> {
+        self.parse_inner().map(|(val, _)| val)
+    }
+}
+
+impl<R: Iterator<char>> Iterator<Result<Value, ParseError>> for Parser<R> {
+    fn next(&mut self) -> Option<Result<Value, ParseError>> {
+        match self.lexer.peek() {
+            None => None,
+            Some(_) => Some(self.parse()),
         }
     }
 }
 
-pub fn parse_str(s: &str) -> Value {
+/// Builds a `Parser` over a character stream, ready to be iterated for
+/// multiple top-level forms.
+pub fn parser<R: Iterator<char>>(iter: std::iter::Peekable<char, R>) -> Parser<R> {
+    let l = Lexer { stream: iter, line: 1, col: 1 };
+    Parser { lexer: l.peekable(), last_pos: Position { line: 1, col: 1 } }
+}
+
+pub fn parse_str(s: &str) -> Result<Value, ParseError> {
     parse(s.chars().peekable())
 }
 
-pub fn parse<R: Iterator<char>>(iter: std::iter::Peekable<char, R>) -> Value {
-    let l = Lexer { stream: iter };
-    let mut p = Parser { lexer: l.peekable(), stack: Vec::new() };
-    p.parse().unwrap()
+pub fn parse<R: Iterator<char>>(iter: std::iter::Peekable<char, R>) -> Result<Value, ParseError> {
+    parser(iter).parse()
+}
+
+/// Like `parse_str`, but keeps the span of the top-level form that was parsed.
+///
+/// Only the outermost form gets a `Span` — a `List`'s elements are plain
+/// `Value`s with no span of their own, so reaching into one won't hand you
+/// per-element source locations.
+pub fn parse_str_spanned(s: &str) -> Result<Spanned<Value>, ParseError> {
+    parse_spanned(s.chars().peekable())
+}
+
+/// Like `parse`, but keeps the span of the top-level form that was parsed.
+///
+/// See `parse_str_spanned` for the caveat on nested forms.
+pub fn parse_spanned<R: Iterator<char>>(iter: std::iter::Peekable<char, R>) -> Result<Spanned<Value>, ParseError> {
+    parser(iter).parse_inner().map(|(val, span)| Spanned { node: val, span: span })
+}
+
+/// Reads every top-level form out of `s`, e.g. `"(a) (b) 42"` yields three `Value`s.
+/// Each form's own span is discarded; `parse_spanned` keeps the span but reads only the first form.
+pub fn parse_all(s: &str) -> Result<Vec<Value>, ParseError> {
+    let mut out = Vec::new();
+    for item in parser(s.chars().peekable()) {
+        out.push(try!(item));
+    }
+    Ok(out)
 }
 
 #[cfg(test)]
 mod test {
     extern crate quickcheck;
 
-    use super::{Value, List, Symbol, Number, String_, parse_str};
+    use super::{Value, List, Symbol, Number, Integer, String_, UnexpectedEof};
+    use super::{parse_str, parse_str_spanned, parse_all, parser};
     use std::rand::Rng;
     use self::quickcheck::{Gen, Arbitrary};
 
     impl Arbitrary for Value {
         fn arbitrary<G: Gen>(g: &mut G) -> Value {
-            match g.gen_range(0i, 4i) {
+            match g.gen_range(0i, 5i) {
                 0 => {
                     return List(Arbitrary::arbitrary(g));
                 },
@@ -193,13 +515,17 @@ mod test {
                 },
                 2 => {
                     let arb: String = Arbitrary::arbitrary(g);
-                    return String_(arb.as_slice().chars().filter(|c| *c != '"').collect());
+                    return String_(arb);
                 },
                 3 => {
                     let arb: i32 = Arbitrary::arbitrary(g);
                     // guaranteed correct stringification, won't need fuzzy equality
                     return Number(arb as f64);
                 }
+                4 => {
+                    let arb: i64 = Arbitrary::arbitrary(g);
+                    return Integer(arb);
+                }
                 _ => unreachable!()
             }
         }
@@ -208,23 +534,99 @@ mod test {
     #[test]
     fn meow() {
         let expected = List(vec!(Symbol("meow".to_string()), List(vec!(Number(42.0)))));
-        let real = parse_str("(meow (42))");
+        let real = parse_str("(meow (42.0))").unwrap();
         assert_eq!(expected, real);
     }
 
     #[test]
     fn negative() {
-        let expected = Number(-42.0);
-        let real = parse_str("-42");
+        let expected = Integer(-42);
+        let real = parse_str("-42").unwrap();
+        assert_eq!(expected, real);
+    }
+
+    #[test]
+    fn negative_float() {
+        let expected = Number(-2.5);
+        let real = parse_str("-2.5").unwrap();
+        assert_eq!(expected, real);
+    }
+
+    #[test]
+    fn exponents_are_numbers() {
+        let expected = Number(2.5e-3);
+        let real = parse_str("2.5e-3").unwrap();
         assert_eq!(expected, real);
     }
 
+    #[test]
+    fn dash_alone_is_a_symbol() {
+        let expected = List(vec!(Symbol("-".to_string()), Integer(1)));
+        let real = parse_str("(- 1)").unwrap();
+        assert_eq!(expected, real);
+    }
+
+    #[test]
+    fn malformed_number_is_an_error() {
+        assert!(parse_str("1.2.3").is_err());
+        assert!(parse_str("1e").is_err());
+    }
+
     #[test]
     fn quick() {
         // tests both correct stringification and that parsing is correct.
         fn prop(val: Value) -> bool {
-            val == parse_str(val.to_string().as_slice())
+            val == parse_str(val.to_string().as_slice()).unwrap()
         }
         quickcheck::quickcheck(prop);
     }
+
+    #[test]
+    fn spans_track_line_and_col() {
+        let spanned = parse_str_spanned("(a\n  b)").unwrap();
+        assert_eq!(spanned.span.start.line, 1u);
+        assert_eq!(spanned.span.start.col, 1u);
+        assert_eq!(spanned.span.end.line, 2u);
+    }
+
+    #[test]
+    fn string_escapes_round_trip() {
+        let expected = String_("a\nb\"c\\d".to_string());
+        let printed = expected.to_string();
+        let real = parse_str(printed.as_slice()).unwrap();
+        assert_eq!(expected, real);
+    }
+
+    #[test]
+    fn malformed_escape_is_an_error() {
+        assert!(parse_str("\"\\q\"").is_err());
+    }
+
+    #[test]
+    fn parse_all_reads_every_top_level_form() {
+        let forms = parse_all("(a) (b) 42").unwrap();
+        assert_eq!(forms, vec!(
+            List(vec!(Symbol("a".to_string()))),
+            List(vec!(Symbol("b".to_string()))),
+            Integer(42),
+        ));
+    }
+
+    #[test]
+    fn a_failed_nested_form_leaves_its_remaining_tokens_to_later_calls() {
+        let mut it = parser("(a @ b) (c)".chars().peekable());
+        assert!(it.next().unwrap().is_err());
+        assert_eq!(it.next().unwrap().unwrap(), Symbol("b".to_string()));
+        assert!(it.next().unwrap().is_err());
+        assert_eq!(it.next().unwrap().unwrap(), List(vec!(Symbol("c".to_string()))));
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn unclosed_list_is_an_eof_error() {
+        match parse_str("(a b") {
+            Err(ref err) => assert_eq!(err.node, UnexpectedEof),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
 }